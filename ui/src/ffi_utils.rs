@@ -0,0 +1,60 @@
+//! Utilities shared by every module that calls into `ui_sys`.
+
+use main_thread;
+use std::ffi::CStr;
+use std::ops::Deref;
+use std::os::raw::c_char;
+use std::str;
+use std::sync::{Once, ONCE_INIT};
+use ui_sys;
+
+static UI_INIT: Once = ONCE_INIT;
+
+pub fn ensure_initialized() {
+    UI_INIT.call_once(|| {
+        unsafe {
+            let mut init_options = ui_sys::uiInitOptions {
+                Size: 0,
+            };
+            let err = ui_sys::uiInit(&mut init_options);
+            if !err.is_null() {
+                let error_string = CStr::from_ptr(err).to_string_lossy().into_owned();
+                ui_sys::uiFreeInitError(err);
+                panic!("failed to initialize libui: {}", error_string);
+            }
+        }
+        main_thread::record_main_thread();
+    })
+}
+
+pub struct Text {
+    ui_text: *mut c_char,
+}
+
+impl Drop for Text {
+    fn drop(&mut self) {
+        unsafe {
+            ui_sys::uiFreeText(self.ui_text)
+        }
+    }
+}
+
+impl Deref for Text {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        unsafe {
+            let c_str = CStr::from_ptr(self.ui_text);
+            str::from_utf8(c_str.to_bytes()).unwrap()
+        }
+    }
+}
+
+impl Text {
+    #[inline]
+    pub unsafe fn new(ui_text: *mut c_char) -> Text {
+        Text {
+            ui_text: ui_text,
+        }
+    }
+}
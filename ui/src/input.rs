@@ -0,0 +1,44 @@
+//! Types describing raw keyboard and mouse input delivered to a `Window`.
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub struct Modifiers {
+    pub ctrl: bool,
+    pub alt: bool,
+    pub shift: bool,
+    pub super_: bool,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum KeyState {
+    Pressed,
+    Released,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct KeyEvent {
+    pub key_code: u32,
+    pub modifiers: Modifiers,
+    pub state: KeyState,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum MouseButton {
+    Left,
+    Middle,
+    Right,
+    Other(u8),
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum MouseEventKind {
+    Down,
+    Up,
+    Move,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct MouseEvent {
+    pub position: (f64, f64),
+    pub button: MouseButton,
+    pub kind: MouseEventKind,
+}
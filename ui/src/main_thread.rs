@@ -0,0 +1,52 @@
+//! Enforcement that windows are only touched from the thread libui was
+//! initialized on, since calling into libui from any other thread is
+//! undefined behavior.
+
+use std::sync::Once;
+use std::thread::{self, ThreadId};
+
+static RECORD_MAIN_THREAD: Once = Once::new();
+static mut MAIN_THREAD: Option<ThreadId> = None;
+
+pub fn record_main_thread() {
+    unsafe {
+        RECORD_MAIN_THREAD.call_once(|| {
+            MAIN_THREAD = Some(thread::current().id());
+        });
+    }
+}
+
+pub fn assert_main_thread() {
+    let current = thread::current().id();
+    match unsafe { MAIN_THREAD } {
+        Some(main) if main == current => {}
+        Some(_) => {
+            panic!("libui call made from thread {:?}, but libui was initialized on a \
+                    different thread; all Window operations must run on the UI thread",
+                   current)
+        }
+        None => {
+            panic!("libui call made before ffi_utils::ensure_initialized() recorded a UI \
+                    thread")
+        }
+    }
+}
+
+pub struct MainThreadToken {
+    _private: (),
+}
+
+impl MainThreadToken {
+    pub fn new() -> MainThreadToken {
+        assert_main_thread();
+        MainThreadToken {
+            _private: (),
+        }
+    }
+}
+
+impl Default for MainThreadToken {
+    fn default() -> MainThreadToken {
+        MainThreadToken::new()
+    }
+}
@@ -0,0 +1,89 @@
+//! Functions and types related to display monitors.
+
+use ffi_utils::{self, Text};
+use libc::c_int;
+use main_thread;
+use ui_sys::{self, uiMonitor};
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct MonitorId(*mut uiMonitor);
+
+pub struct Monitor {
+    ui_monitor: *mut uiMonitor,
+}
+
+impl Monitor {
+    #[inline]
+    pub fn id(&self) -> MonitorId {
+        MonitorId(self.ui_monitor)
+    }
+
+    #[inline]
+    pub fn as_ui_monitor(&self) -> *mut uiMonitor {
+        self.ui_monitor
+    }
+
+    #[inline]
+    pub fn name(&self) -> Text {
+        ffi_utils::ensure_initialized();
+        main_thread::assert_main_thread();
+        unsafe {
+            Text::new(ui_sys::uiMonitorName(self.ui_monitor))
+        }
+    }
+
+    #[inline]
+    pub fn position(&self) -> (i32, i32) {
+        ffi_utils::ensure_initialized();
+        main_thread::assert_main_thread();
+        let mut x: c_int = 0;
+        let mut y: c_int = 0;
+        unsafe {
+            ui_sys::uiMonitorPosition(self.ui_monitor, &mut x, &mut y);
+        }
+        (x, y)
+    }
+
+    #[inline]
+    pub fn size(&self) -> (i32, i32) {
+        ffi_utils::ensure_initialized();
+        main_thread::assert_main_thread();
+        let mut width: c_int = 0;
+        let mut height: c_int = 0;
+        unsafe {
+            ui_sys::uiMonitorSize(self.ui_monitor, &mut width, &mut height);
+        }
+        (width, height)
+    }
+
+    #[inline]
+    pub unsafe fn from_ui_monitor(monitor: *mut uiMonitor) -> Monitor {
+        Monitor {
+            ui_monitor: monitor,
+        }
+    }
+}
+
+#[inline]
+pub fn available_monitors() -> Vec<Monitor> {
+    ffi_utils::ensure_initialized();
+    main_thread::assert_main_thread();
+    unsafe {
+        let mut count: c_int = 0;
+        let monitors = ui_sys::uiMonitorsList(&mut count);
+        let result = (0..count as isize)
+            .map(|i| Monitor::from_ui_monitor(*monitors.offset(i)))
+            .collect();
+        ui_sys::uiFreeMonitorsList(monitors);
+        result
+    }
+}
+
+#[inline]
+pub fn primary_monitor() -> Monitor {
+    ffi_utils::ensure_initialized();
+    main_thread::assert_main_thread();
+    unsafe {
+        Monitor::from_ui_monitor(ui_sys::uiMonitorPrimary())
+    }
+}
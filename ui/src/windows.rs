@@ -2,16 +2,26 @@
 
 use controls::Control;
 use ffi_utils::{self, Text};
+use input::{KeyEvent, KeyState, Modifiers, MouseButton, MouseEvent, MouseEventKind};
 use libc::{c_int, c_void};
+use main_thread;
+use monitors::Monitor;
 use std::cell::RefCell;
+use std::collections::HashMap;
 use std::ffi::CString;
 use std::mem;
-use ui_sys::{self, uiControl, uiWindow};
+use std::ptr;
+use ui_sys::{self, uiControl, uiImage, uiWindow};
 
 thread_local! {
     static WINDOWS: RefCell<Vec<Window>> = RefCell::new(Vec::new())
 }
 
+thread_local! {
+    static WINDOW_ICONS: RefCell<HashMap<*mut uiWindow, *mut uiImage>> =
+        RefCell::new(HashMap::new())
+}
+
 define_control!(Window, uiWindow, ui_window);
 
 impl Window {
@@ -23,6 +33,7 @@ impl Window {
     #[inline]
     pub fn title(&self) -> Text {
         ffi_utils::ensure_initialized();
+        main_thread::assert_main_thread();
         unsafe {
             Text::new(ui_sys::uiWindowTitle(self.ui_window))
         }
@@ -31,6 +42,7 @@ impl Window {
     #[inline]
     pub fn set_title(&self, title: &str) {
         ffi_utils::ensure_initialized();
+        main_thread::assert_main_thread();
         unsafe {
             let c_string = CString::new(title.as_bytes().to_vec()).unwrap();
             ui_sys::uiWindowSetTitle(self.ui_window, c_string.as_ptr())
@@ -40,6 +52,7 @@ impl Window {
     #[inline]
     pub fn position(&self) -> (i32, i32) {
         ffi_utils::ensure_initialized();
+        main_thread::assert_main_thread();
         let mut x: c_int = 0;
         let mut y: c_int = 0;
         unsafe {
@@ -51,6 +64,7 @@ impl Window {
     #[inline]
     pub fn set_position(&self, x: i32, y: i32) {
         ffi_utils::ensure_initialized();
+        main_thread::assert_main_thread();
         unsafe {
             ui_sys::uiWindowSetPosition(self.ui_window, x as c_int, y as c_int)
         }
@@ -59,6 +73,7 @@ impl Window {
     #[inline]
     pub fn center(&self) {
         ffi_utils::ensure_initialized();
+        main_thread::assert_main_thread();
         unsafe {
             ui_sys::uiWindowCenter(self.ui_window)
         }
@@ -67,6 +82,7 @@ impl Window {
     #[inline]
     pub fn on_position_changed(&self, callback: Box<FnMut(&Window)>) {
         ffi_utils::ensure_initialized();
+        main_thread::assert_main_thread();
         unsafe {
             let mut data: Box<Box<FnMut(&Window)>> = Box::new(callback);
             ui_sys::uiWindowOnPositionChanged(self.ui_window,
@@ -89,6 +105,7 @@ impl Window {
     #[inline]
     pub fn content_size(&self) -> (i32, i32) {
         ffi_utils::ensure_initialized();
+        main_thread::assert_main_thread();
         let mut width: c_int = 0;
         let mut height: c_int = 0;
         unsafe {
@@ -100,14 +117,64 @@ impl Window {
     #[inline]
     pub fn set_content_size(&self, width: i32, height: i32) {
         ffi_utils::ensure_initialized();
+        main_thread::assert_main_thread();
         unsafe {
             ui_sys::uiWindowSetContentSize(self.ui_window, width as c_int, height as c_int)
         }
     }
 
+    #[inline]
+    pub fn scale_factor(&self) -> f64 {
+        ffi_utils::ensure_initialized();
+        main_thread::assert_main_thread();
+        unsafe {
+            ui_sys::uiWindowScaleFactor(self.ui_window)
+        }
+    }
+
+    #[inline]
+    pub fn logical_to_physical(&self, logical: (f64, f64)) -> (f64, f64) {
+        let scale_factor = self.scale_factor();
+        (logical.0 * scale_factor, logical.1 * scale_factor)
+    }
+
+    #[inline]
+    pub fn physical_to_logical(&self, physical: (f64, f64)) -> (f64, f64) {
+        let scale_factor = self.scale_factor();
+        if scale_factor == 0.0 {
+            return (0.0, 0.0);
+        }
+        (physical.0 / scale_factor, physical.1 / scale_factor)
+    }
+
+    #[inline]
+    pub fn on_scale_changed(&self, callback: Box<FnMut(&Window, f64)>) {
+        ffi_utils::ensure_initialized();
+        main_thread::assert_main_thread();
+        unsafe {
+            let mut data: Box<Box<FnMut(&Window, f64)>> = Box::new(callback);
+            ui_sys::uiWindowOnScaleChanged(self.ui_window,
+                                           c_callback,
+                                           &mut *data as *mut Box<FnMut(&Window, f64)> as
+                                           *mut c_void);
+            mem::forget(data);
+        }
+
+        extern "C" fn c_callback(window: *mut uiWindow, scale_factor: f64, data: *mut c_void) {
+            unsafe {
+                let window = Window {
+                    ui_window: window,
+                };
+                mem::transmute::<*mut c_void, &mut Box<FnMut(&Window, f64)>>(data)(&window,
+                                                                                   scale_factor)
+            }
+        }
+    }
+
     #[inline]
     pub fn fullscreen(&self) -> bool {
         ffi_utils::ensure_initialized();
+        main_thread::assert_main_thread();
         unsafe {
             ui_sys::uiWindowFullscreen(self.ui_window) != 0
         }
@@ -116,14 +183,51 @@ impl Window {
     #[inline]
     pub fn set_fullscreen(&self, fullscreen: bool) {
         ffi_utils::ensure_initialized();
+        main_thread::assert_main_thread();
         unsafe {
             ui_sys::uiWindowSetFullscreen(self.ui_window, fullscreen as c_int)
         }
     }
 
+    #[inline]
+    pub fn current_monitor(&self) -> Option<Monitor> {
+        ffi_utils::ensure_initialized();
+        main_thread::assert_main_thread();
+        unsafe {
+            let ui_monitor = ui_sys::uiWindowMonitor(self.ui_window);
+            if ui_monitor.is_null() {
+                None
+            } else {
+                Some(Monitor::from_ui_monitor(ui_monitor))
+            }
+        }
+    }
+
+    #[inline]
+    pub fn set_position_on(&self, monitor: &Monitor, x: i32, y: i32) {
+        ffi_utils::ensure_initialized();
+        main_thread::assert_main_thread();
+        unsafe {
+            ui_sys::uiWindowSetPositionOnMonitor(self.ui_window,
+                                                 monitor.as_ui_monitor(),
+                                                 x as c_int,
+                                                 y as c_int)
+        }
+    }
+
+    #[inline]
+    pub fn set_fullscreen_on(&self, monitor: &Monitor) {
+        ffi_utils::ensure_initialized();
+        main_thread::assert_main_thread();
+        unsafe {
+            ui_sys::uiWindowSetFullscreenOnMonitor(self.ui_window, monitor.as_ui_monitor())
+        }
+    }
+
     #[inline]
     pub fn on_content_size_changed(&self, callback: Box<FnMut(&Window)>) {
         ffi_utils::ensure_initialized();
+        main_thread::assert_main_thread();
         unsafe {
             let mut data: Box<Box<FnMut(&Window)>> = Box::new(callback);
             ui_sys::uiWindowOnContentSizeChanged(self.ui_window,
@@ -146,6 +250,7 @@ impl Window {
     #[inline]
     pub fn on_closing(&self, callback: Box<FnMut(&Window) -> bool>) {
         ffi_utils::ensure_initialized();
+        main_thread::assert_main_thread();
         unsafe {
             let mut data: Box<Box<FnMut(&Window) -> bool>> = Box::new(callback);
             ui_sys::uiWindowOnClosing(self.ui_window,
@@ -166,9 +271,86 @@ impl Window {
         }
     }
 
+    #[inline]
+    pub fn on_key_event(&self, callback: Box<FnMut(&Window, KeyEvent) -> bool>) {
+        ffi_utils::ensure_initialized();
+        main_thread::assert_main_thread();
+        unsafe {
+            let mut data: Box<Box<FnMut(&Window, KeyEvent) -> bool>> = Box::new(callback);
+            ui_sys::uiWindowOnKeyEvent(self.ui_window,
+                                       c_callback,
+                                       &mut *data as
+                                       *mut Box<FnMut(&Window, KeyEvent) -> bool> as
+                                       *mut c_void);
+            mem::forget(data);
+        }
+
+        extern "C" fn c_callback(window: *mut uiWindow,
+                                  key_code: u32,
+                                  modifiers: c_int,
+                                  pressed: c_int,
+                                  data: *mut c_void)
+                                  -> i32 {
+            unsafe {
+                let window = Window {
+                    ui_window: window,
+                };
+                let event = KeyEvent {
+                    key_code: key_code,
+                    modifiers: decode_modifiers(modifiers),
+                    state: if pressed != 0 {
+                        KeyState::Pressed
+                    } else {
+                        KeyState::Released
+                    },
+                };
+                mem::transmute::<*mut c_void,
+                                 &mut Box<FnMut(&Window, KeyEvent) -> bool>>(data)(&window,
+                                                                                   event) as
+                i32
+            }
+        }
+    }
+
+    #[inline]
+    pub fn on_mouse_event(&self, callback: Box<FnMut(&Window, MouseEvent)>) {
+        ffi_utils::ensure_initialized();
+        main_thread::assert_main_thread();
+        unsafe {
+            let mut data: Box<Box<FnMut(&Window, MouseEvent)>> = Box::new(callback);
+            ui_sys::uiWindowOnMouseEvent(self.ui_window,
+                                         c_callback,
+                                         &mut *data as
+                                         *mut Box<FnMut(&Window, MouseEvent)> as
+                                         *mut c_void);
+            mem::forget(data);
+        }
+
+        extern "C" fn c_callback(window: *mut uiWindow,
+                                  x: f64,
+                                  y: f64,
+                                  button: c_int,
+                                  kind: c_int,
+                                  data: *mut c_void) {
+            unsafe {
+                let window = Window {
+                    ui_window: window,
+                };
+                let event = MouseEvent {
+                    position: (x, y),
+                    button: decode_mouse_button(button),
+                    kind: decode_mouse_event_kind(kind),
+                };
+                mem::transmute::<*mut c_void,
+                                 &mut Box<FnMut(&Window, MouseEvent)>>(data)(&window, event)
+            }
+        }
+    }
+
     #[inline]
     pub fn borderless(&self) -> bool {
         ffi_utils::ensure_initialized();
+        main_thread::assert_main_thread();
         unsafe {
             ui_sys::uiWindowBorderless(self.ui_window) != 0
         }
@@ -177,6 +359,7 @@ impl Window {
     #[inline]
     pub fn set_borderless(&self, borderless: bool) {
         ffi_utils::ensure_initialized();
+        main_thread::assert_main_thread();
         unsafe {
             ui_sys::uiWindowSetBorderless(self.ui_window, borderless as c_int)
         }
@@ -185,6 +368,7 @@ impl Window {
     #[inline]
     pub fn set_child(&self, child: Control) {
         ffi_utils::ensure_initialized();
+        main_thread::assert_main_thread();
         unsafe {
             ui_sys::uiWindowSetChild(self.ui_window, child.as_ui_control())
         }
@@ -193,6 +377,7 @@ impl Window {
     #[inline]
     pub fn margined(&self) -> bool {
         ffi_utils::ensure_initialized();
+        main_thread::assert_main_thread();
         unsafe {
             ui_sys::uiWindowMargined(self.ui_window) != 0
         }
@@ -201,14 +386,58 @@ impl Window {
     #[inline]
     pub fn set_margined(&self, margined: bool) {
         ffi_utils::ensure_initialized();
+        main_thread::assert_main_thread();
         unsafe {
             ui_sys::uiWindowSetMargined(self.ui_window, margined as c_int)
         }
     }
 
+    #[inline]
+    pub fn set_icon(&self, width: u32, height: u32, rgba: &[u8]) {
+        ffi_utils::ensure_initialized();
+        main_thread::assert_main_thread();
+        let stride = width as usize * 4;
+        assert_eq!(rgba.len(),
+                   stride * height as usize,
+                   "rgba buffer must hold width * height * 4 bytes");
+        unsafe {
+            let image = ui_sys::uiNewImage(width as f64, height as f64);
+            ui_sys::uiImageAppend(image,
+                                  rgba.as_ptr() as *mut c_void,
+                                  width as c_int,
+                                  height as c_int,
+                                  stride as c_int);
+            ui_sys::uiWindowSetIcon(self.ui_window, image);
+            self.replace_icon(Some(image));
+        }
+    }
+
+    #[inline]
+    pub fn clear_icon(&self) {
+        ffi_utils::ensure_initialized();
+        main_thread::assert_main_thread();
+        unsafe {
+            ui_sys::uiWindowSetIcon(self.ui_window, ptr::null_mut());
+            self.replace_icon(None);
+        }
+    }
+
+    unsafe fn replace_icon(&self, image: Option<*mut uiImage>) {
+        let previous = WINDOW_ICONS.with(|icons| {
+            match image {
+                Some(image) => icons.borrow_mut().insert(self.ui_window, image),
+                None => icons.borrow_mut().remove(&self.ui_window),
+            }
+        });
+        if let Some(previous) = previous {
+            ui_sys::uiFreeImage(previous);
+        }
+    }
+
     #[inline]
     pub fn new(title: &str, width: c_int, height: c_int, has_menubar: bool) -> Window {
         ffi_utils::ensure_initialized();
+        main_thread::assert_main_thread();
         unsafe {
             let c_string = CString::new(title.as_bytes().to_vec()).unwrap();
             let window = Window::from_ui_window(ui_sys::uiNewWindow(c_string.as_ptr(),
@@ -229,12 +458,147 @@ impl Window {
         }
     }
 
-    pub unsafe fn destroy_all_windows() {
+    pub fn destroy_all_windows() {
+        main_thread::assert_main_thread();
         WINDOWS.with(|windows| {
             let mut windows = windows.borrow_mut();
             for window in windows.drain(..) {
-                window.destroy()
+                unsafe {
+                    window.replace_icon(None);
+                    window.destroy()
+                }
             }
         })
     }
 }
+
+pub struct WindowBuilder {
+    title: String,
+    width: c_int,
+    height: c_int,
+    has_menubar: bool,
+    position: Option<(i32, i32)>,
+    fullscreen: bool,
+    borderless: bool,
+    margined: bool,
+    child: Option<Control>,
+}
+
+impl WindowBuilder {
+    #[inline]
+    pub fn new(title: &str) -> WindowBuilder {
+        WindowBuilder {
+            title: title.to_owned(),
+            width: 640,
+            height: 480,
+            has_menubar: false,
+            position: None,
+            fullscreen: false,
+            borderless: false,
+            margined: false,
+            child: None,
+        }
+    }
+
+    #[inline]
+    pub fn title(mut self, title: &str) -> WindowBuilder {
+        self.title = title.to_owned();
+        self
+    }
+
+    #[inline]
+    pub fn content_size(mut self, width: i32, height: i32) -> WindowBuilder {
+        self.width = width as c_int;
+        self.height = height as c_int;
+        self
+    }
+
+    #[inline]
+    pub fn position(mut self, x: i32, y: i32) -> WindowBuilder {
+        self.position = Some((x, y));
+        self
+    }
+
+    #[inline]
+    pub fn fullscreen(mut self, fullscreen: bool) -> WindowBuilder {
+        self.fullscreen = fullscreen;
+        self
+    }
+
+    #[inline]
+    pub fn borderless(mut self, borderless: bool) -> WindowBuilder {
+        self.borderless = borderless;
+        self
+    }
+
+    #[inline]
+    pub fn margined(mut self, margined: bool) -> WindowBuilder {
+        self.margined = margined;
+        self
+    }
+
+    #[inline]
+    pub fn menubar(mut self, has_menubar: bool) -> WindowBuilder {
+        self.has_menubar = has_menubar;
+        self
+    }
+
+    #[inline]
+    pub fn child(mut self, child: Control) -> WindowBuilder {
+        self.child = Some(child);
+        self
+    }
+
+    pub fn build(self) -> Window {
+        let window = Window::new(&self.title, self.width, self.height, self.has_menubar);
+
+        if let Some((x, y)) = self.position {
+            window.set_position(x, y);
+        }
+        if self.fullscreen {
+            window.set_fullscreen(true);
+        }
+        if self.borderless {
+            window.set_borderless(true);
+        }
+        if self.margined {
+            window.set_margined(true);
+        }
+        if let Some(child) = self.child {
+            window.set_child(child);
+        }
+
+        window
+    }
+}
+
+const MODIFIER_CTRL: c_int = 1 << 0;
+const MODIFIER_ALT: c_int = 1 << 1;
+const MODIFIER_SHIFT: c_int = 1 << 2;
+const MODIFIER_SUPER: c_int = 1 << 3;
+
+fn decode_modifiers(modifiers: c_int) -> Modifiers {
+    Modifiers {
+        ctrl: modifiers & MODIFIER_CTRL != 0,
+        alt: modifiers & MODIFIER_ALT != 0,
+        shift: modifiers & MODIFIER_SHIFT != 0,
+        super_: modifiers & MODIFIER_SUPER != 0,
+    }
+}
+
+fn decode_mouse_button(button: c_int) -> MouseButton {
+    match button {
+        0 => MouseButton::Left,
+        1 => MouseButton::Middle,
+        2 => MouseButton::Right,
+        other => MouseButton::Other(other as u8),
+    }
+}
+
+fn decode_mouse_event_kind(kind: c_int) -> MouseEventKind {
+    match kind {
+        0 => MouseEventKind::Down,
+        1 => MouseEventKind::Up,
+        _ => MouseEventKind::Move,
+    }
+}